@@ -0,0 +1,220 @@
+use bulwark_wasm_sdk::*;
+use std::collections::HashMap;
+
+pub struct ContentTypeMismatch;
+
+/// The number of leading body bytes we inspect when sniffing the payload.
+const SNIFF_LEN: usize = 512;
+
+/// Score for an image declared as one format whose bytes sniff as a different image, and for
+/// a non-image declared type whose bytes sniff as a different known binary format.
+const IMAGE_MISMATCH_SCORE: f64 = 0.25;
+/// Score for a declared image type whose bytes sniff as a non-image binary format.
+const BINARY_MISMATCH_SCORE: f64 = 0.5;
+/// Score for HTML/script bytes served where markup is not expected.
+const MARKUP_SCORE: f64 = 0.5;
+/// Score for HTML/script bytes declared as an image or `text/plain` (XSS polyglot).
+const POLYGLOT_SCORE: f64 = 0.9;
+
+/// A media type inferred from the leading bytes of a request body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sniffed {
+    Png,
+    Gif,
+    Jpeg,
+    Pdf,
+    Zip,
+    /// HTML, SVG, XML or an inline `<script>` payload.
+    Markup,
+    /// The bytes did not match any known signature.
+    Unknown,
+}
+
+impl Sniffed {
+    /// The canonical media type for the sniffed format, ignoring parameters.
+    fn media_type(self) -> Option<&'static str> {
+        match self {
+            Sniffed::Png => Some("image/png"),
+            Sniffed::Gif => Some("image/gif"),
+            Sniffed::Jpeg => Some("image/jpeg"),
+            Sniffed::Pdf => Some("application/pdf"),
+            Sniffed::Zip => Some("application/zip"),
+            Sniffed::Markup => Some("text/html"),
+            Sniffed::Unknown => None,
+        }
+    }
+
+    /// Whether the sniffed format is a raster image.
+    fn is_image(self) -> bool {
+        matches!(self, Sniffed::Png | Sniffed::Gif | Sniffed::Jpeg)
+    }
+}
+
+/// The leading byte signatures we match against, longest-first so that more specific
+/// signatures win. HTML/script is handled separately because it is whitespace-tolerant.
+const SIGNATURES: &[(&[u8], Sniffed)] = &[
+    (&[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A], Sniffed::Png),
+    (b"GIF87a", Sniffed::Gif),
+    (b"GIF89a", Sniffed::Gif),
+    (b"%PDF-", Sniffed::Pdf),
+    (&[0x50, 0x4B, 0x03, 0x04], Sniffed::Zip),
+    (&[0xFF, 0xD8, 0xFF], Sniffed::Jpeg),
+];
+
+/// Case-insensitive markup tokens scanned at the start of the whitespace-trimmed body.
+const MARKUP_TOKENS: &[&[u8]] = &[
+    b"<!doctype html",
+    b"<html",
+    b"<script",
+    b"<svg",
+    b"<?xml",
+];
+
+/// Classifies the leading bytes of a body against the signature table.
+fn sniff(body: &[u8]) -> Sniffed {
+    let head = &body[..body.len().min(SNIFF_LEN)];
+    for (signature, sniffed) in SIGNATURES {
+        if head.starts_with(signature) {
+            return *sniffed;
+        }
+    }
+    let trimmed = trim_ascii_start(head);
+    for token in MARKUP_TOKENS {
+        if starts_with_ignore_ascii_case(trimmed, token) {
+            return Sniffed::Markup;
+        }
+    }
+    Sniffed::Unknown
+}
+
+/// Drops leading ASCII whitespace without allocating.
+fn trim_ascii_start(bytes: &[u8]) -> &[u8] {
+    let mut start = 0;
+    while start < bytes.len() && bytes[start].is_ascii_whitespace() {
+        start += 1;
+    }
+    &bytes[start..]
+}
+
+/// Case-insensitive ASCII prefix match.
+fn starts_with_ignore_ascii_case(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.len() >= needle.len()
+        && haystack[..needle.len()].eq_ignore_ascii_case(needle)
+}
+
+/// The essence of a declared `Content-Type`: the media type with parameters stripped.
+fn declared_media_type(content_type: &HeaderValue) -> Option<String> {
+    let value = content_type.to_str().ok()?;
+    Some(
+        value
+            .split(';')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase(),
+    )
+}
+
+/// Scores a declared media type against the bytes sniffed from the body. The matrix favours
+/// high scores for markup smuggled into binary uploads and low scores for image/image confusion.
+fn score_mismatch(declared: &str, sniffed: Sniffed) -> f64 {
+    if sniffed == Sniffed::Unknown {
+        return 0.0;
+    }
+    let declared_image = declared.starts_with("image/");
+    match sniffed {
+        Sniffed::Markup => {
+            if declared_image || declared == "text/plain" {
+                POLYGLOT_SCORE
+            } else if declared == "text/html"
+                || declared == "image/svg+xml"
+                || declared == "application/xml"
+                || declared == "text/xml"
+            {
+                0.0
+            } else {
+                MARKUP_SCORE
+            }
+        }
+        _ => {
+            if Some(declared) == sniffed.media_type() {
+                0.0
+            } else if declared_image && sniffed.is_image() {
+                IMAGE_MISMATCH_SCORE
+            } else if declared_image {
+                BINARY_MISMATCH_SCORE
+            } else {
+                IMAGE_MISMATCH_SCORE
+            }
+        }
+    }
+}
+
+#[bulwark_plugin]
+impl HttpHandlers for ContentTypeMismatch {
+    fn handle_request_body_decision(
+        request: Request,
+        _params: HashMap<String, String>,
+    ) -> Result<HandlerOutput, Error> {
+        let mut output = HandlerOutput::default();
+        let Some(content_type) = request.headers().get("Content-Type") else {
+            return Ok(output);
+        };
+        let Some(declared) = declared_media_type(content_type) else {
+            return Ok(output);
+        };
+        let sniffed = sniff(&request.body().content);
+        let score = score_mismatch(&declared, sniffed);
+        if score > 0.0 {
+            output.tags = vec!["content-type-mismatch".to_string()];
+        }
+        output.decision = Decision::restricted(score);
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_sniff() {
+        let test_cases: Vec<(&[u8], Sniffed)> = vec![
+            (&[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A], Sniffed::Png),
+            (b"GIF89a....", Sniffed::Gif),
+            (b"GIF87a....", Sniffed::Gif),
+            (&[0xFF, 0xD8, 0xFF, 0xE0], Sniffed::Jpeg),
+            (b"%PDF-1.7", Sniffed::Pdf),
+            (&[0x50, 0x4B, 0x03, 0x04], Sniffed::Zip),
+            (b"   <!DOCTYPE html><html>", Sniffed::Markup),
+            (b"<ScRiPt>alert(1)</script>", Sniffed::Markup),
+            (b"\n\t<svg onload=alert(1)>", Sniffed::Markup),
+            (b"<?xml version=\"1.0\"?>", Sniffed::Markup),
+            (b"just some plain text", Sniffed::Unknown),
+            (b"", Sniffed::Unknown),
+        ];
+        for (body, expected) in test_cases {
+            assert_eq!(sniff(body), expected);
+        }
+    }
+
+    #[test]
+    fn test_score_mismatch() {
+        // Matching declarations score nothing.
+        assert_relative_eq!(score_mismatch("image/png", Sniffed::Png), 0.0);
+        assert_relative_eq!(score_mismatch("text/html", Sniffed::Markup), 0.0);
+        // Image declared, bytes are a different image.
+        assert_relative_eq!(
+            score_mismatch("image/png", Sniffed::Jpeg),
+            IMAGE_MISMATCH_SCORE
+        );
+        // Image declared, bytes are a non-image binary.
+        assert_relative_eq!(score_mismatch("image/png", Sniffed::Zip), BINARY_MISMATCH_SCORE);
+        // The classic XSS polyglot: markup declared as an image or plain text.
+        assert_relative_eq!(score_mismatch("image/jpeg", Sniffed::Markup), POLYGLOT_SCORE);
+        assert_relative_eq!(score_mismatch("text/plain", Sniffed::Markup), POLYGLOT_SCORE);
+        // Unknown bytes are never scored.
+        assert_relative_eq!(score_mismatch("image/png", Sniffed::Unknown), 0.0);
+    }
+}
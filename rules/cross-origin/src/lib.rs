@@ -0,0 +1,192 @@
+use anyhow::anyhow;
+use bulwark_wasm_sdk::*;
+
+struct CrossOriginPlugin;
+
+/// Moderate score applied when a state-changing request carries neither an `Origin`
+/// nor a `Referer` header and therefore cannot be cross-site validated.
+const DEFAULT_MISSING_SCORE: f64 = 0.5;
+
+/// Whether the method can change server state and is therefore worth cross-site checking.
+fn is_state_changing(method: &http::Method) -> bool {
+    matches!(
+        *method,
+        http::Method::POST | http::Method::PUT | http::Method::PATCH | http::Method::DELETE
+    )
+}
+
+/// The host and port halves of an origin. The port is part of the origin for a
+/// same-origin check, so we keep it rather than collapsing to host only.
+type Origin = (String, Option<String>);
+
+/// Splits an authority component into its lowercased host and optional port, preserving
+/// bracketed IPv6 literals.
+fn parse_authority(authority: &str) -> Option<Origin> {
+    let authority = authority.trim();
+    if authority.is_empty() {
+        return None;
+    }
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        // IPv6 literal: keep everything up to and including the closing bracket as the host.
+        let end = rest.find(']')?;
+        let host = &authority[..end + 2];
+        let port = authority[end + 2..].strip_prefix(':');
+        (host, port)
+    } else {
+        match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    if host.is_empty() {
+        None
+    } else {
+        Some((
+            host.to_ascii_lowercase(),
+            port.filter(|p| !p.is_empty()).map(|p| p.to_string()),
+        ))
+    }
+}
+
+/// Parses the host and port from an `Origin` header or a full `Referer` URL. The scheme is
+/// consumed but not retained, since the `Host` header we compare against carries no scheme.
+fn url_host(value: &str) -> Option<Origin> {
+    let value = value.trim();
+    if value.is_empty() || value == "null" {
+        return None;
+    }
+    let after_scheme = value.split_once("://").map(|(_, rest)| rest).unwrap_or(value);
+    let authority = after_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or(after_scheme);
+    // Origins and referers never carry userinfo, but strip it defensively if present.
+    let authority = authority.rsplit_once('@').map(|(_, a)| a).unwrap_or(authority);
+    parse_authority(authority)
+}
+
+/// Matches a host against a single allowlist entry, honouring a leading-dot wildcard
+/// such as `.example.com`, which covers both `example.com` and any subdomain of it.
+fn host_matches(host: &str, allowed: &str) -> bool {
+    let allowed = allowed.trim().to_ascii_lowercase();
+    if let Some(suffix) = allowed.strip_prefix('.') {
+        host == suffix || host.ends_with(&allowed)
+    } else {
+        host == allowed
+    }
+}
+
+/// Reads the configured allowlist of origin hostnames, if any.
+fn allowed_origins(value: Option<Value>) -> Vec<String> {
+    value
+        .and_then(|value| value.as_array().cloned())
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[bulwark_plugin]
+impl Handlers for CrossOriginPlugin {
+    fn on_request_decision() -> Result {
+        let request = get_request();
+        if !is_state_changing(request.method()) {
+            return Ok(());
+        }
+
+        let source_host = request
+            .headers()
+            .get("Origin")
+            .and_then(|hv| hv.to_str().ok())
+            .and_then(url_host)
+            .or_else(|| {
+                request
+                    .headers()
+                    .get("Referer")
+                    .and_then(|hv| hv.to_str().ok())
+                    .and_then(url_host)
+            });
+
+        let Some((source_host, source_port)) = source_host else {
+            // Neither header present on a mutating request: we cannot attribute the origin.
+            append_tags(["missing-origin"]);
+            let score = get_config_value("missing")
+                .and_then(|value| value.as_f64())
+                .unwrap_or(DEFAULT_MISSING_SCORE);
+            set_restricted(score);
+            return Ok(());
+        };
+
+        let allowed = allowed_origins(get_config_value("allowed_origins"));
+        let permitted = if allowed.is_empty() {
+            // Same-origin default: host and port must both match the request's own Host header.
+            request
+                .headers()
+                .get("Host")
+                .and_then(|hv| hv.to_str().ok())
+                .and_then(parse_authority)
+                .map(|(host, port)| host == source_host && port == source_port)
+                .unwrap_or(false)
+        } else {
+            // The allowlist is a set of hostnames, so it is matched on host alone.
+            allowed.iter().any(|allowed| host_matches(&source_host, allowed))
+        };
+
+        if !permitted {
+            append_tags(["cross-origin"]);
+            if let Some(restrict_increment) = get_config_value("restrict") {
+                let restrict_increment = restrict_increment
+                    .as_f64()
+                    .ok_or(anyhow!("restrict must be f64"))?;
+                set_restricted(restrict_increment)
+            } else if let Some(accept_increment) = get_config_value("accept") {
+                let accept_increment = accept_increment
+                    .as_f64()
+                    .ok_or(anyhow!("accept must be f64"))?;
+                set_restricted(accept_increment)
+            } else {
+                return Err(anyhow!("no accept or restrict increment specified"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_host() {
+        let test_cases = [
+            ("https://example.com", Some(("example.com", None))),
+            ("https://example.com:8443", Some(("example.com", Some("8443")))),
+            ("http://example.com:80", Some(("example.com", Some("80")))),
+            ("http://Example.COM/path?q=1", Some(("example.com", None))),
+            ("https://sub.example.com/login", Some(("sub.example.com", None))),
+            ("https://[2001:db8::1]:443/", Some(("[2001:db8::1]", Some("443")))),
+            ("null", None),
+            ("", None),
+        ];
+        for (input, expected) in test_cases {
+            let expected = expected.map(|(host, port)| {
+                (host.to_string(), port.map(|p: &str| p.to_string()))
+            });
+            assert_eq!(url_host(input), expected);
+        }
+    }
+
+    #[test]
+    fn test_host_matches() {
+        assert!(host_matches("example.com", "example.com"));
+        assert!(!host_matches("evil.com", "example.com"));
+        // Leading-dot wildcard covers the apex and any subdomain.
+        assert!(host_matches("example.com", ".example.com"));
+        assert!(host_matches("api.example.com", ".example.com"));
+        assert!(!host_matches("example.com.evil.com", ".example.com"));
+        assert!(!host_matches("notexample.com", ".example.com"));
+    }
+}
@@ -0,0 +1,170 @@
+use bulwark_wasm_sdk::*;
+
+/// A soft limit applies once a request carries more than 100 header fields.
+const DEFAULT_COUNT_SOFT: u64 = 100;
+/// The hard limit on the number of header fields.
+const DEFAULT_COUNT_HARD: u64 = 200;
+
+/// A soft limit applies once any single header value exceeds 8 KiB.
+const DEFAULT_VALUE_SOFT: u64 = 8 * 1024;
+/// The hard limit on the length of any single header value.
+const DEFAULT_VALUE_HARD: u64 = 16 * 1024;
+
+/// A soft limit applies once the cumulative header bytes exceed 16 KiB.
+const DEFAULT_TOTAL_SOFT: u64 = 16 * 1024;
+/// The hard limit on the cumulative byte length of all headers.
+const DEFAULT_TOTAL_HARD: u64 = 64 * 1024;
+
+/// A soft limit applies once the request URI exceeds 8 KiB.
+const DEFAULT_URI_SOFT: u64 = 8 * 1024;
+/// The hard limit on the request URI length.
+const DEFAULT_URI_HARD: u64 = 16 * 1024;
+
+// This generally will not result in a restrict decision in isolation
+const DEFAULT_SOFT_WEIGHT: f64 = 0.15;
+
+// Hitting a hard limit will give maximum block weight
+const DEFAULT_HARD_WEIGHT: f64 = 1.0;
+
+#[derive(Debug, PartialEq, Eq)]
+enum LimitLevel {
+    Normal,
+    SoftLimit,
+    HardLimit,
+}
+
+/// Checks a measured value against its soft and hard thresholds.
+fn check_limit(soft_limit: u64, hard_limit: u64, value: u64) -> LimitLevel {
+    match value {
+        x if x > hard_limit => LimitLevel::HardLimit,
+        x if x > soft_limit => LimitLevel::SoftLimit,
+        _ => LimitLevel::Normal,
+    }
+}
+
+/// Determine the restrict weight based on the limit level.
+fn weight_limit(soft_weight: Option<Value>, hard_weight: Option<Value>, level: LimitLevel) -> f64 {
+    match level {
+        LimitLevel::HardLimit => hard_weight
+            .and_then(|value| value.as_f64())
+            .unwrap_or(DEFAULT_HARD_WEIGHT),
+        LimitLevel::SoftLimit => soft_weight
+            .and_then(|value| value.as_f64())
+            .unwrap_or(DEFAULT_SOFT_WEIGHT),
+        LimitLevel::Normal => 0.0,
+    }
+}
+
+/// Reads a configured `u64` threshold, falling back to a default.
+fn threshold(key: &str, default: u64) -> u64 {
+    get_config_value(key)
+        .and_then(|value| value.as_u64())
+        .unwrap_or(default)
+}
+
+/// The metadata dimensions we measure about a request.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct HeaderMetrics {
+    count: u64,
+    total_bytes: u64,
+    longest_value: u64,
+    uri_length: u64,
+}
+
+/// Measures the header-count, cumulative header-size, longest header value and URI length.
+fn measure(request: &Request) -> HeaderMetrics {
+    let mut metrics = HeaderMetrics {
+        uri_length: request.uri().to_string().len() as u64,
+        ..Default::default()
+    };
+    for (name, value) in request.headers() {
+        metrics.count += 1;
+        let value_len = value.as_bytes().len() as u64;
+        metrics.total_bytes += name.as_str().len() as u64 + value_len;
+        metrics.longest_value = metrics.longest_value.max(value_len);
+    }
+    metrics
+}
+
+struct HeaderLimitPlugin;
+
+#[bulwark_plugin]
+impl Handlers for HeaderLimitPlugin {
+    fn on_request_decision() -> Result {
+        let request = get_request();
+        let metrics = measure(&request);
+
+        let dimensions = [
+            (
+                check_limit(
+                    threshold("max_header_count", DEFAULT_COUNT_SOFT),
+                    threshold("max_header_count_hard", DEFAULT_COUNT_HARD),
+                    metrics.count,
+                ),
+                "excessive-headers",
+            ),
+            (
+                check_limit(
+                    threshold("max_total_header_size", DEFAULT_TOTAL_SOFT),
+                    threshold("max_total_header_size_hard", DEFAULT_TOTAL_HARD),
+                    metrics.total_bytes,
+                ),
+                "excessive-header-bytes",
+            ),
+            (
+                check_limit(
+                    threshold("max_header_value_size", DEFAULT_VALUE_SOFT),
+                    threshold("max_header_value_size_hard", DEFAULT_VALUE_HARD),
+                    metrics.longest_value,
+                ),
+                "long-header-value",
+            ),
+            (
+                check_limit(
+                    threshold("max_uri_length", DEFAULT_URI_SOFT),
+                    threshold("max_uri_length_hard", DEFAULT_URI_HARD),
+                    metrics.uri_length,
+                ),
+                "long-uri",
+            ),
+        ];
+
+        // `set_restricted` replaces the decision rather than accumulating, so aggregate
+        // every breached dimension to its maximum weight and restrict exactly once.
+        let mut weight = 0.0;
+        for (level, tag) in dimensions {
+            let dimension_weight = weight_limit(
+                get_config_value("soft_weight"),
+                get_config_value("hard_weight"),
+                level,
+            );
+            if dimension_weight > 0.0 {
+                append_tags([tag]);
+                weight = f64::max(weight, dimension_weight);
+            }
+        }
+        if weight > 0.0 {
+            set_restricted(weight);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_limit() {
+        let test_cases = [
+            (100, 200, 0, LimitLevel::Normal),
+            (100, 200, 100, LimitLevel::Normal),
+            (100, 200, 101, LimitLevel::SoftLimit),
+            (100, 200, 200, LimitLevel::SoftLimit),
+            (100, 200, 201, LimitLevel::HardLimit),
+        ];
+        for (soft, hard, value, expected) in test_cases {
+            assert_eq!(check_limit(soft, hard, value), expected);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use bulwark_wasm_sdk::*;
+
+struct RangeAbusePlugin;
+
+/// Default ceiling on the number of byte ranges before a request looks like an
+/// amplification attempt rather than a legitimate multi-part fetch.
+const DEFAULT_MAX_RANGES: u64 = 8;
+
+/// Suspicion score applied to syntactically malformed `Range` headers.
+const MALFORMED_SCORE: f64 = 0.25;
+/// Score contributed by each range beyond the configured maximum.
+const COUNT_WEIGHT: f64 = 0.1;
+/// Score contributed when the range set overlaps or is unsorted.
+const OVERLAP_WEIGHT: f64 = 0.4;
+/// Largest score contributed when the covered bytes exceed the implied resource size; it is
+/// scaled by the excess ratio and reached once the covered bytes are twice that size.
+const AMPLIFY_WEIGHT: f64 = 0.4;
+/// The maximum score any single request can accumulate.
+const MAX_SCORE: f64 = 1.0;
+
+/// A single `start-end` entry from a byte-range-set, with either bound optional.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ByteRange {
+    start: Option<u64>,
+    end: Option<u64>,
+}
+
+/// Parses a `bytes=` byte-range-set. Returns `None` when the syntax is malformed, including
+/// a unit with no ranges (`bytes=`); a successful parse always holds at least one range.
+fn parse_ranges(value: &str) -> Option<Vec<ByteRange>> {
+    let value = value.trim();
+    let spec = value.strip_prefix("bytes=")?;
+    let mut ranges = Vec::new();
+    for part in spec.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            // A stray comma (e.g. `bytes=0-1,,2-3`) is malformed.
+            return None;
+        }
+        let (start, end) = part.split_once('-')?;
+        let start = parse_bound(start)?;
+        let end = parse_bound(end)?;
+        if start.is_none() && end.is_none() {
+            return None;
+        }
+        ranges.push(ByteRange { start, end });
+    }
+    Some(ranges)
+}
+
+/// Parses one side of a range, treating the empty string as an absent bound.
+fn parse_bound(bound: &str) -> Option<Option<u64>> {
+    let bound = bound.trim();
+    if bound.is_empty() {
+        Some(None)
+    } else {
+        bound.parse::<u64>().ok().map(Some)
+    }
+}
+
+/// Resolves a range to an inclusive `(start, end)` interval, using the resource size
+/// implied by `Content-Length` to close open-ended and suffix ranges.
+fn resolve(range: &ByteRange, content_length: Option<u64>) -> (u64, u64) {
+    let last = content_length.map(|cl| cl.saturating_sub(1));
+    match (range.start, range.end) {
+        (Some(start), Some(end)) => (start, end.max(start)),
+        (Some(start), None) => (start, last.unwrap_or(u64::MAX)),
+        (None, Some(suffix)) => (
+            content_length.map(|cl| cl.saturating_sub(suffix)).unwrap_or(0),
+            last.unwrap_or(u64::MAX),
+        ),
+        (None, None) => (0, u64::MAX),
+    }
+}
+
+/// Whether the resolved ranges overlap one another or appear out of ascending order,
+/// both hallmarks of the multi-range memory-amplification attack.
+fn overlaps_or_unsorted(resolved: &[(u64, u64)]) -> bool {
+    resolved.windows(2).any(|pair| {
+        let (prev_start, prev_end) = pair[0];
+        let (start, _) = pair[1];
+        start <= prev_end || start < prev_start
+    })
+}
+
+/// The total number of bytes covered by the resolved ranges, saturating on overflow.
+fn covered_bytes(resolved: &[(u64, u64)]) -> u64 {
+    resolved.iter().fold(0u64, |acc, (start, end)| {
+        acc.saturating_add(end.saturating_sub(*start).saturating_add(1))
+    })
+}
+
+/// Scores a parsed range set against the configured maximum and the implied resource size.
+fn score_ranges(ranges: &[ByteRange], content_length: Option<u64>, max_ranges: u64) -> f64 {
+    let count = ranges.len() as u64;
+    if count <= 1 {
+        return 0.0;
+    }
+
+    let resolved: Vec<(u64, u64)> = ranges.iter().map(|r| resolve(r, content_length)).collect();
+    let mut score = 0.0;
+
+    if count > max_ranges {
+        score += COUNT_WEIGHT * (count - max_ranges) as f64;
+    }
+    if overlaps_or_unsorted(&resolved) {
+        score += OVERLAP_WEIGHT;
+    }
+    if let Some(cl) = content_length.filter(|cl| *cl > 0) {
+        let ratio = covered_bytes(&resolved) as f64 / cl as f64;
+        if ratio > 1.0 {
+            score += AMPLIFY_WEIGHT * (ratio - 1.0).min(1.0);
+        }
+    }
+
+    score.min(MAX_SCORE)
+}
+
+/// Reads the configured maximum range count, falling back to the default.
+fn max_ranges(value: Option<Value>) -> u64 {
+    value
+        .and_then(|value| value.as_u64())
+        .unwrap_or(DEFAULT_MAX_RANGES)
+}
+
+#[bulwark_plugin]
+impl Handlers for RangeAbusePlugin {
+    fn on_request_decision() -> Result {
+        let request = get_request();
+        let header = request
+            .headers()
+            .get("Range")
+            .or_else(|| request.headers().get("Request-Range"));
+        let Some(header) = header.and_then(|hv| hv.to_str().ok()) else {
+            return Ok(());
+        };
+
+        let content_length = request
+            .headers()
+            .get("Content-Length")
+            .and_then(|hv| hv.to_str().ok())
+            .and_then(|hv| hv.parse().ok());
+
+        match parse_ranges(header) {
+            Some(ranges) => {
+                let score = score_ranges(&ranges, content_length, max_ranges(get_config_value("max_ranges")));
+                if score > 0.0 {
+                    append_tags(["range-abuse"]);
+                    set_restricted(score);
+                }
+            }
+            None => {
+                append_tags(["range-abuse"]);
+                set_restricted(MALFORMED_SCORE);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_parse_ranges() {
+        assert_eq!(
+            parse_ranges("bytes=0-499"),
+            Some(vec![ByteRange {
+                start: Some(0),
+                end: Some(499)
+            }])
+        );
+        assert_eq!(
+            parse_ranges("bytes=500-, -200"),
+            Some(vec![
+                ByteRange {
+                    start: Some(500),
+                    end: None
+                },
+                ByteRange {
+                    start: None,
+                    end: Some(200)
+                }
+            ])
+        );
+        // Malformed: no unit, empty entry, or a dash with no bounds.
+        assert_eq!(parse_ranges("0-499"), None);
+        assert_eq!(parse_ranges("bytes=0-1,,2-3"), None);
+        assert_eq!(parse_ranges("bytes=-"), None);
+        assert_eq!(parse_ranges("bytes=abc-def"), None);
+    }
+
+    #[test]
+    fn test_score_ranges() {
+        let max = DEFAULT_MAX_RANGES;
+        // A single benign range scores nothing.
+        assert_relative_eq!(
+            score_ranges(&parse_ranges("bytes=0-499").unwrap(), Some(1000), max),
+            0.0
+        );
+        // A classic overlapping amplification set scores highly.
+        let overlapping = parse_ranges("bytes=0-,0-1,0-2,0-3,0-4").unwrap();
+        assert!(score_ranges(&overlapping, Some(1000), max) >= OVERLAP_WEIGHT);
+        // Exceeding the maximum range count pushes the score toward the cap.
+        let many = parse_ranges("bytes=0-0,1-1,2-2,3-3,4-4,5-5,6-6,7-7,8-8,9-9").unwrap();
+        assert!(score_ranges(&many, Some(1000), max) > 0.0);
+        // The score never exceeds the clamp.
+        assert!(score_ranges(&overlapping, Some(1), max) <= MAX_SCORE);
+    }
+}
@@ -1,9 +1,63 @@
 use anyhow::anyhow;
 use bulwark_wasm_sdk::*;
-use regex::RegexSetBuilder;
+use regex::{RegexSet, RegexSetBuilder};
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
 struct RegexPlugin;
 
+thread_local! {
+    /// The most recently compiled `RegexSet`, keyed by a hash of the configuration it was
+    /// built from. Plugin instances are single-threaded, so this caches the compiled set
+    /// across requests and only recompiles when the `patterns`/`case_insensitive` config
+    /// actually changes.
+    static REGEX_CACHE: RefCell<Option<(u64, RegexSet)>> = const { RefCell::new(None) };
+}
+
+/// A fast, non-cryptographic hash over the configured patterns and case-sensitivity flag,
+/// used as the cache key for the compiled `RegexSet`.
+fn config_hash(patterns: &[String], case_insensitive: bool) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    case_insensitive.hash(&mut hasher);
+    for pattern in patterns {
+        pattern.hash(&mut hasher);
+        // Delimiter so `["ab", "c"]` and `["a", "bc"]` hash differently.
+        0xffu8.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Counts regex-set matches across the sources, compiling the `RegexSet` at most once per
+/// distinct configuration and reusing the cached set on every subsequent request.
+fn count_matches(
+    sources: Vec<Vec<u8>>,
+    patterns: Vec<String>,
+    case_insensitive: bool,
+) -> std::result::Result<usize, Error> {
+    let hash = config_hash(&patterns, case_insensitive);
+    REGEX_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if !matches!(cache.as_ref(), Some((cached_hash, _)) if *cached_hash == hash) {
+            let regex_set = RegexSetBuilder::new(patterns)
+                .case_insensitive(case_insensitive)
+                .build()?;
+            *cache = Some((hash, regex_set));
+        }
+        let regex_set = &cache.as_ref().expect("cache was just populated").1;
+        let mut match_count = 0;
+        for source in sources {
+            if let Ok(haystack) = std::str::from_utf8(source.as_slice()) {
+                let matches = regex_set.matches(haystack);
+                if matches.matched_any() {
+                    match_count += matches.iter().count();
+                }
+            }
+        }
+        Ok(match_count)
+    })
+}
+
 fn get_sources(value: Option<Value>, request: Request) -> std::result::Result<Vec<Vec<u8>>, Error> {
     let value = value.unwrap_or_else(|| Value::String(String::from("all")));
     let mut sources = vec![];
@@ -85,16 +139,10 @@ fn get_patterns(value: Option<Value>) -> std::result::Result<Vec<String>, Error>
 fn regex_handler() -> Result {
     let sources = get_sources(get_config_value("location"), get_request())?;
     let patterns = get_patterns(get_config_value("patterns"))?;
-    let regex_set = RegexSetBuilder::new(patterns).build()?;
-    let mut match_count = 0;
-    for source in sources {
-        if let Ok(haystack) = std::str::from_utf8(source.as_slice()) {
-            let matches = regex_set.matches(haystack);
-            if matches.matched_any() {
-                match_count += matches.iter().count();
-            }
-        }
-    }
+    let case_insensitive = get_config_value("case_insensitive")
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false);
+    let match_count = count_matches(sources, patterns, case_insensitive)?;
     if match_count > 0 {
         if let Some(restrict_increment) = get_config_value("restrict") {
             let restrict_increment = restrict_increment